@@ -1,13 +1,21 @@
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
+use keyring::Entry;
 use log::{debug, error, info};
 use regex::Regex;
 use reqwest;
+use secrecy::{ExposeSecret, SecretString};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::process::Command;
 use std::time::Duration;
 use thiserror::Error;
 use url::Url;
 
+mod server;
+
+/// Keychain service name under which Pi-hole credentials are stored, keyed by host.
+const PIHOLE_KEYCHAIN_SERVICE: &str = "netscene-pihole";
+
 /// Representation of a network device discovered on the local network.
 #[derive(Debug, Serialize, Clone, PartialEq, Eq)]
 pub struct Device {
@@ -41,12 +49,219 @@ struct PiholeSession {
     validity: u64,
 }
 
-/// Pi-hole authentication request
-#[derive(Debug, Serialize)]
+/// Pi-hole authentication request.
+///
+/// Does not derive `Debug` so the password can never end up in a log line;
+/// the plaintext only exists here, built via [`ExposeSecret::expose_secret`]
+/// at the point the request body is serialized.
+#[derive(Serialize)]
 struct PiholeAuthRequest {
     password: String,
 }
 
+/// Credential used to authenticate with a Pi-hole instance.
+///
+/// `Token` targets older (v5) installs that expect the legacy `&auth=<token>`
+/// query parameter. `Password` targets v6's password-for-session exchange via
+/// `/api/auth`, and is wrapped in a [`SecretString`] so it is redacted from
+/// `Debug` and zeroized on drop. `None` is used for Pi-holes with
+/// authentication disabled.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "lowercase")]
+pub enum PiholeCredential {
+    None,
+    Token(String),
+    Password(SecretString),
+}
+
+/// Current blocking state of a Pi-hole instance, as returned after toggling it.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PiholeBlockingState {
+    pub blocking: bool,
+    /// Seconds until blocking automatically re-enables, if a timer was set.
+    pub timer: Option<u64>,
+}
+
+/// Body for the v6 `POST /api/dns/blocking` request.
+#[derive(Debug, Serialize)]
+struct PiholeBlockingRequest {
+    blocking: bool,
+    timer: Option<u64>,
+}
+
+/// Wire shape of the v6 `POST /api/dns/blocking` response, which encodes
+/// `blocking` as `"enabled"`/`"disabled"` rather than a bool.
+#[derive(Debug, Deserialize)]
+struct RawPiholeBlockingStateV6 {
+    blocking: String,
+    timer: Option<u64>,
+}
+
+impl From<RawPiholeBlockingStateV6> for PiholeBlockingState {
+    fn from(raw: RawPiholeBlockingStateV6) -> Self {
+        PiholeBlockingState {
+            blocking: raw.blocking == "enabled",
+            timer: raw.timer,
+        }
+    }
+}
+
+/// Most-queried domains and most-blocked domains, ordered by count (highest first).
+#[derive(Debug, Serialize, Clone)]
+pub struct PiholeTopItems {
+    pub top_queries: Vec<(String, u64)>,
+    pub top_ads: Vec<(String, u64)>,
+}
+
+/// Wire shape of the legacy `?topItems` endpoint.
+#[derive(Debug, Deserialize)]
+struct RawPiholeTopItems {
+    top_queries: HashMap<String, u64>,
+    top_ads: HashMap<String, u64>,
+}
+
+impl From<RawPiholeTopItems> for PiholeTopItems {
+    fn from(raw: RawPiholeTopItems) -> Self {
+        let mut top_queries: Vec<(String, u64)> = raw.top_queries.into_iter().collect();
+        top_queries.sort_by(|a, b| b.1.cmp(&a.1));
+
+        let mut top_ads: Vec<(String, u64)> = raw.top_ads.into_iter().collect();
+        top_ads.sort_by(|a, b| b.1.cmp(&a.1));
+
+        PiholeTopItems { top_queries, top_ads }
+    }
+}
+
+/// Wire shape of a single entry returned by the v6 `/api/stats/top_domains` endpoint.
+#[derive(Debug, Deserialize)]
+struct RawPiholeTopDomainV6 {
+    domain: String,
+    count: u64,
+}
+
+/// Wire shape of the v6 `/api/stats/top_domains` endpoint. Permitted and
+/// blocked domains live behind the same path, distinguished by a `blocked`
+/// query parameter, so this shape is fetched twice to build [`PiholeTopItems`].
+#[derive(Debug, Deserialize)]
+struct RawPiholeTopDomainsV6 {
+    domains: Vec<RawPiholeTopDomainV6>,
+}
+
+fn top_domains_v6_to_series(raw: RawPiholeTopDomainsV6) -> Vec<(String, u64)> {
+    let mut series: Vec<(String, u64)> = raw
+        .domains
+        .into_iter()
+        .map(|entry| (entry.domain, entry.count))
+        .collect();
+    series.sort_by(|a, b| b.1.cmp(&a.1));
+    series
+}
+
+/// Percentage of queries by DNS record type (e.g. "A (IPv4)", "AAAA (IPv6)").
+#[derive(Debug, Serialize, Clone)]
+pub struct PiholeQueryTypes(pub HashMap<String, f64>);
+
+/// Wire shape of the legacy `?getQueryTypes` endpoint.
+#[derive(Debug, Deserialize)]
+struct RawPiholeQueryTypes {
+    querytypes: HashMap<String, f64>,
+}
+
+/// Wire shape of the v6 `/api/stats/query_types` endpoint. Unlike the legacy
+/// payload, `types` holds raw query counts rather than percentages, so
+/// callers must normalize via [`counts_to_percentages`] before treating it as
+/// a [`PiholeQueryTypes`].
+#[derive(Debug, Deserialize)]
+struct RawPiholeQueryTypesV6 {
+    types: HashMap<String, f64>,
+}
+
+/// Convert per-type query counts into percentages of the total, so v6's count
+/// payload matches the percentage contract [`PiholeQueryTypes`] shares with
+/// the legacy API (and that [`validate_query_types`] checks sums to ~100%).
+fn counts_to_percentages(counts: HashMap<String, f64>) -> HashMap<String, f64> {
+    let total: f64 = counts.values().sum();
+    if total <= 0.0 {
+        return counts;
+    }
+    counts.into_iter().map(|(dns_type, count)| (dns_type, count / total * 100.0)).collect()
+}
+
+/// Query and ad-block counts bucketed into time slots, as `(unix_timestamp, count)` pairs.
+#[derive(Debug, Serialize, Clone)]
+pub struct PiholeOverTime {
+    pub domains_over_time: Vec<(u64, u64)>,
+    pub ads_over_time: Vec<(u64, u64)>,
+}
+
+/// Wire shape of the legacy `?overTimeData10mins` endpoint.
+#[derive(Debug, Deserialize)]
+struct RawPiholeOverTime {
+    domains_over_time: HashMap<String, u64>,
+    ads_over_time: HashMap<String, u64>,
+}
+
+fn over_time_from_raw(raw: RawPiholeOverTime) -> Result<PiholeOverTime, PiholeError> {
+    fn to_sorted_series(map: HashMap<String, u64>) -> Result<Vec<(u64, u64)>, PiholeError> {
+        let mut series = map
+            .into_iter()
+            .map(|(timestamp, count)| {
+                timestamp
+                    .parse::<u64>()
+                    .map(|ts| (ts, count))
+                    .map_err(|_| PiholeError::JsonError(format!("Invalid over-time timestamp: {}", timestamp)))
+            })
+            .collect::<Result<Vec<(u64, u64)>, PiholeError>>()?;
+        series.sort_by_key(|(timestamp, _)| *timestamp);
+        Ok(series)
+    }
+
+    Ok(PiholeOverTime {
+        domains_over_time: to_sorted_series(raw.domains_over_time)?,
+        ads_over_time: to_sorted_series(raw.ads_over_time)?,
+    })
+}
+
+/// Wire shape of a single bucket in the v6 `/api/history` endpoint.
+#[derive(Debug, Deserialize)]
+struct RawPiholeHistoryEntryV6 {
+    timestamp: u64,
+    total: u64,
+    blocked: u64,
+}
+
+/// Wire shape of the v6 `/api/history` endpoint.
+#[derive(Debug, Deserialize)]
+struct RawPiholeOverTimeV6 {
+    history: Vec<RawPiholeHistoryEntryV6>,
+}
+
+fn over_time_from_raw_v6(raw: RawPiholeOverTimeV6) -> PiholeOverTime {
+    let mut domains_over_time: Vec<(u64, u64)> = raw
+        .history
+        .iter()
+        .map(|entry| (entry.timestamp, entry.total))
+        .collect();
+    domains_over_time.sort_by_key(|(timestamp, _)| *timestamp);
+
+    let mut ads_over_time: Vec<(u64, u64)> = raw
+        .history
+        .iter()
+        .map(|entry| (entry.timestamp, entry.blocked))
+        .collect();
+    ads_over_time.sort_by_key(|(timestamp, _)| *timestamp);
+
+    PiholeOverTime { domains_over_time, ads_over_time }
+}
+
+/// Combined rich telemetry used to draw charts in the UI.
+#[derive(Debug, Serialize, Clone)]
+pub struct PiholeDetails {
+    pub top_items: PiholeTopItems,
+    pub query_types: PiholeQueryTypes,
+    pub over_time: PiholeOverTime,
+}
+
 /// Custom error types for better error handling
 #[derive(Error, Debug)]
 pub enum PiholeError {
@@ -62,6 +277,8 @@ pub enum PiholeError {
     ServerError { status: u16 },
     #[error("Response validation failed: {reason}")]
     ValidationError { reason: String },
+    #[error("Keychain operation failed: {0}")]
+    KeychainError(String),
 }
 
 impl From<PiholeError> for String {
@@ -112,6 +329,11 @@ pub fn parse_arp_output(output: &str) -> Vec<Device> {
 /// command execution are converted into strings.
 #[tauri::command]
 async fn scan_network() -> Result<Vec<Device>, String> {
+    scan_network_internal().await.map_err(|e| e.into())
+}
+
+/// Internal function for testing and reuse - scans the network without the Tauri command wrapper
+pub(crate) async fn scan_network_internal() -> Result<Vec<Device>, NetworkScanError> {
     info!("Running arp -a to scan network");
     let output = Command::new("arp")
         .arg("-a")
@@ -145,8 +367,12 @@ fn validate_pihole_response(stats: &PiholeStats) -> Result<(), PiholeError> {
     Ok(())
 }
 
-/// Parse and validate a host string, attempting both legacy and new API endpoints
-fn parse_pihole_urls(host: &str) -> Result<(Url, Url), PiholeError> {
+/// Parse and validate a host string, attempting both legacy and new API endpoints.
+///
+/// When `credential` is a [`PiholeCredential::Token`], the legacy URL's query
+/// is extended with `&auth=<token>` so the legacy endpoint can authenticate
+/// without the v6 session exchange.
+pub(crate) fn parse_pihole_urls(host: &str, credential: &PiholeCredential) -> Result<(Url, Url), PiholeError> {
     let trimmed_host = host.trim();
 
     if trimmed_host.is_empty() {
@@ -166,7 +392,11 @@ fn parse_pihole_urls(host: &str) -> Result<(Url, Url), PiholeError> {
     // Legacy API endpoint
     let mut legacy_url = base_url.clone();
     legacy_url.set_path("/admin/api.php");
-    legacy_url.set_query(Some("summaryRaw"));
+    if let PiholeCredential::Token(token) = credential {
+        legacy_url.set_query(Some(&append_auth_token("summaryRaw", token)));
+    } else {
+        legacy_url.set_query(Some("summaryRaw"));
+    }
 
     // New API endpoint
     let mut new_url = base_url.clone();
@@ -174,19 +404,22 @@ fn parse_pihole_urls(host: &str) -> Result<(Url, Url), PiholeError> {
 
     debug!("Legacy Pi-hole URL: {}", legacy_url);
     debug!("New Pi-hole URL: {}", new_url);
-    
+
     Ok((legacy_url, new_url))
 }
 
 /// Authenticate with Pi-hole to get session ID
-async fn authenticate_pihole(host: &str, password: Option<&str>) -> Result<Option<String>, PiholeError> {
+async fn authenticate_pihole(
+    host: &str,
+    password: Option<&SecretString>,
+) -> Result<Option<String>, PiholeError> {
     if password.is_none() {
         return Ok(None);
     }
 
     let password = password.unwrap();
     let trimmed_host = host.trim();
-    
+
     let url_string = if trimmed_host.starts_with("http://") || trimmed_host.starts_with("https://") {
         trimmed_host.to_string()
     } else {
@@ -198,7 +431,7 @@ async fn authenticate_pihole(host: &str, password: Option<&str>) -> Result<Optio
 
     let client = create_http_client();
     let auth_request = PiholeAuthRequest {
-        password: password.to_string(),
+        password: password.expose_secret().to_string(),
     };
 
     debug!("Attempting authentication with: {}", auth_url);
@@ -219,6 +452,27 @@ async fn authenticate_pihole(host: &str, password: Option<&str>) -> Result<Optio
     }
 }
 
+/// Build a Pi-hole URL for `path`, normalizing the host the same way
+/// [`parse_pihole_urls`] and [`authenticate_pihole`] do.
+fn build_pihole_url(host: &str, path: &str) -> Result<Url, PiholeError> {
+    let trimmed_host = host.trim();
+
+    if trimmed_host.is_empty() {
+        return Err(PiholeError::InvalidHost("Host cannot be empty".to_string()));
+    }
+
+    let url_string = if trimmed_host.starts_with("http://") || trimmed_host.starts_with("https://")
+    {
+        trimmed_host.to_string()
+    } else {
+        format!("http://{}", trimmed_host)
+    };
+
+    let mut url = Url::parse(&url_string)?;
+    url.set_path(path);
+    Ok(url)
+}
+
 /// Create a configured HTTP client for Pi-hole requests
 fn create_http_client() -> reqwest::Client {
     reqwest::Client::builder()
@@ -229,16 +483,161 @@ fn create_http_client() -> reqwest::Client {
 }
 
 /// Fetch statistics from the Pi-hole instance at the given host.
-/// Supports both legacy and new API formats with optional authentication.
+/// Supports both legacy and new API formats, authenticating with either a
+/// legacy API token or a v6 password, via [`PiholeCredential`].
+#[tauri::command]
+async fn get_pihole_stats(host: &str, credential: PiholeCredential) -> Result<PiholeStats, String> {
+    get_pihole_stats_internal(host, credential).await.map_err(|e| e.into())
+}
+
+/// Fetch rich Pi-hole telemetry (top domains, top blocked domains, query types,
+/// and query/ad history over time) for charting in the UI.
+#[tauri::command]
+async fn get_pihole_details(host: &str, credential: PiholeCredential) -> Result<PiholeDetails, String> {
+    get_pihole_details_internal(host, credential).await.map_err(|e| e.into())
+}
+
+/// Turn Pi-hole ad-blocking on or off, optionally auto-reenabling after `timer` seconds.
+/// Tries the v6 session API first and falls back to the legacy token-based endpoint,
+/// authenticating with either a legacy API token or a v6 password via [`PiholeCredential`].
 #[tauri::command]
-async fn get_pihole_stats(host: &str, password: Option<String>) -> Result<PiholeStats, String> {
-    get_pihole_stats_internal(host, password.as_deref()).await.map_err(|e| e.into())
+async fn set_pihole_blocking(
+    host: &str,
+    credential: PiholeCredential,
+    blocking: bool,
+    timer: Option<u64>,
+) -> Result<PiholeBlockingState, String> {
+    set_pihole_blocking_internal(host, credential, blocking, timer)
+        .await
+        .map_err(|e| e.into())
+}
+
+/// Internal function for testing - sets Pi-hole blocking state without the Tauri command wrapper
+pub async fn set_pihole_blocking_internal(
+    host: &str,
+    credential: PiholeCredential,
+    blocking: bool,
+    timer: Option<u64>,
+) -> Result<PiholeBlockingState, PiholeError> {
+    info!(
+        "Setting Pi-hole blocking state on {}: blocking={}, timer={:?}",
+        host, blocking, timer
+    );
+
+    let sid = resolve_pihole_sid(host, &credential).await;
+    let client = create_http_client();
+
+    if let Some(ref session_id) = sid {
+        let v6_url = build_pihole_url(host, "/api/dns/blocking")?;
+        debug!("Trying v6 blocking endpoint: {}", v6_url);
+
+        let response = client
+            .post(v6_url)
+            .header("X-FTL-SID", session_id)
+            .json(&PiholeBlockingRequest { blocking, timer })
+            .send()
+            .await?;
+
+        if response.status().is_success() {
+            let response_text = response.text().await?;
+            match serde_json::from_str::<RawPiholeBlockingStateV6>(&response_text) {
+                Ok(raw) => {
+                    let state: PiholeBlockingState = raw.into();
+                    info!(
+                        "Pi-hole blocking state updated via v6 API: blocking={}",
+                        state.blocking
+                    );
+                    return Ok(state);
+                }
+                Err(e) => {
+                    debug!(
+                        "v6 blocking endpoint returned unparseable response: {}, trying legacy endpoint",
+                        e
+                    );
+                }
+            }
+        } else {
+            debug!(
+                "v6 blocking endpoint returned non-success status: {}, trying legacy endpoint",
+                response.status()
+            );
+        }
+    }
+
+    // Legacy API fallback
+    let query = if blocking {
+        "enable".to_string()
+    } else {
+        match timer {
+            Some(seconds) => format!("disable={}", seconds),
+            None => "disable".to_string(),
+        }
+    };
+    let legacy_url = build_pihole_legacy_url(host, &query, &credential)?;
+
+    debug!("Trying legacy blocking endpoint: {}", legacy_url);
+
+    let response = client.get(legacy_url).send().await?;
+
+    if !response.status().is_success() {
+        return Err(PiholeError::ServerError {
+            status: response.status().as_u16(),
+        });
+    }
+
+    info!("Pi-hole blocking state updated via legacy API: blocking={}", blocking);
+    Ok(PiholeBlockingState { blocking, timer })
+}
+
+/// Store a Pi-hole password in the OS keychain, keyed by host. No Pi-hole
+/// command reads the stored password back to authenticate; see
+/// [`load_pihole_credential`].
+#[tauri::command]
+async fn store_pihole_credential(host: &str, password: SecretString) -> Result<(), String> {
+    store_pihole_credential_internal(host, &password).map_err(|e| e.into())
+}
+
+fn store_pihole_credential_internal(host: &str, password: &SecretString) -> Result<(), PiholeError> {
+    let entry = Entry::new(PIHOLE_KEYCHAIN_SERVICE, host)
+        .map_err(|e| PiholeError::KeychainError(e.to_string()))?;
+    entry
+        .set_password(password.expose_secret())
+        .map_err(|e| PiholeError::KeychainError(e.to_string()))?;
+    info!("Stored Pi-hole credential for host: {}", host);
+    Ok(())
+}
+
+/// Check whether a Pi-hole password is stored in the OS keychain for `host`.
+///
+/// Only reports presence, not the password itself, so the plaintext is never
+/// returned over IPC. The stored password is not used by any Pi-hole command:
+/// each one (e.g. [`set_pihole_blocking`]) authenticates only with the
+/// [`PiholeCredential`] it is passed.
+#[tauri::command]
+async fn load_pihole_credential(host: &str) -> Result<bool, String> {
+    load_pihole_credential_internal(host)
+        .map(|secret| secret.is_some())
+        .map_err(|e| e.into())
+}
+
+fn load_pihole_credential_internal(host: &str) -> Result<Option<SecretString>, PiholeError> {
+    let entry = Entry::new(PIHOLE_KEYCHAIN_SERVICE, host)
+        .map_err(|e| PiholeError::KeychainError(e.to_string()))?;
+
+    match entry.get_password() {
+        Ok(password) => Ok(Some(SecretString::new(password.into_boxed_str()))),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(PiholeError::KeychainError(e.to_string())),
+    }
 }
 
 /// Internal function for testing - parses host URLs for both legacy and new API
 #[cfg(test)]
-pub fn parse_pihole_urls_internal(host: &str) -> Result<(Url, Url), PiholeError> {
-    parse_pihole_urls(host)
+pub fn parse_pihole_urls_internal(
+    host: &str,
+    credential: &PiholeCredential,
+) -> Result<(Url, Url), PiholeError> {
+    parse_pihole_urls(host, credential)
 }
 
 /// Internal function for testing - validates pihole response
@@ -247,100 +646,92 @@ pub fn validate_pihole_response_internal(stats: &PiholeStats) -> Result<(), Piho
     validate_pihole_response(stats)
 }
 
+/// Internal function for testing - validates Pi-hole query type percentages
+#[cfg(test)]
+pub fn validate_query_types_internal(query_types: &PiholeQueryTypes) -> Result<(), PiholeError> {
+    validate_query_types(query_types)
+}
+
 /// Internal function for testing - parses host (for legacy compatibility)
 #[cfg(test)]
-pub fn parse_host_internal(host: &str) -> Result<Url, PiholeError> {
-    let (legacy_url, _) = parse_pihole_urls(host)?;
+pub fn parse_host_internal(host: &str, credential: &PiholeCredential) -> Result<Url, PiholeError> {
+    let (legacy_url, _) = parse_pihole_urls(host, credential)?;
     Ok(legacy_url)
 }
 
-/// Internal function for testing - gets pihole stats without Tauri command wrapper
-pub async fn get_pihole_stats_internal(host: &str, password: Option<&str>) -> Result<PiholeStats, PiholeError> {
-    info!("Requesting Pi-hole stats from host: {}", host);
-
-    let (legacy_url, new_url) = parse_pihole_urls(host)?;
-    let client = create_http_client();
+/// Fetch `url` and return its body if the response is successful, non-empty,
+/// and not an HTML login page. Used by [`fetch_pihole_response_text`] and by
+/// the per-endpoint detail fetchers, which need to fall back to the legacy
+/// endpoint on a JSON-parse failure as well as on these HTTP-level failures.
+async fn fetch_pihole_url_text(
+    client: &reqwest::Client,
+    sid: Option<&str>,
+    api_type: &str,
+    url: &Url,
+) -> Result<String, PiholeError> {
+    debug!("Trying {} endpoint: {}", api_type, url);
+
+    let mut request = client.get(url.clone());
+
+    // Add authentication if we have a session ID
+    if let Some(session_id) = sid {
+        request = request.header("X-FTL-SID", session_id);
+    }
 
-    // Try to authenticate if password is provided
-    let sid = if password.is_some() {
-        match authenticate_pihole(host, password).await {
-            Ok(sid) => sid,
-            Err(e) => {
-                debug!("Authentication failed, continuing without auth: {}", e);
-                None
-            }
-        }
-    } else {
-        None
-    };
+    let response = request.send().await?;
+    let status = response.status();
+    debug!("{} response status: {}", api_type, status);
 
-    // Try new API first, then fall back to legacy API
-    let endpoints = [
-        ("new API", new_url),
-        ("legacy API", legacy_url),
-    ];
-
-    for (api_type, url) in endpoints.iter() {
-        debug!("Trying {} endpoint: {}", api_type, url);
-        
-        let mut request = client.get(url.clone());
-        
-        // Add authentication if we have a session ID
-        if let Some(ref session_id) = sid {
-            request = request.header("X-FTL-SID", session_id);
-        }
+    if !status.is_success() {
+        return Err(PiholeError::JsonError(format!(
+            "{} returned non-success status: {}",
+            api_type, status
+        )));
+    }
 
-        match request.send().await {
-            Ok(response) => {
-                let status = response.status();
-                debug!("{} response status: {}", api_type, status);
+    let response_text = response.text().await?;
+    debug!("{} response body length: {} bytes", api_type, response_text.len());
 
-                if !status.is_success() {
-                    debug!("{} returned non-success status: {}, trying next endpoint", api_type, status);
-                    continue;
-                }
+    if response_text.is_empty() {
+        return Err(PiholeError::JsonError(format!("{} returned an empty response", api_type)));
+    }
 
-                let response_text = response.text().await?;
-                debug!("{} response body length: {} bytes", api_type, response_text.len());
-                
-                if response_text.is_empty() {
-                    debug!("{} returned empty response, trying next endpoint", api_type);
-                    continue;
-                }
+    // Log first 200 characters of response for debugging
+    let preview = if response_text.len() > 200 {
+        format!("{}...", &response_text[..200])
+    } else {
+        response_text.clone()
+    };
+    debug!("{} response preview: {}", api_type, preview);
+
+    // Check if response is HTML (likely a login page)
+    if response_text.trim_start().starts_with("<!DOCTYPE") || response_text.trim_start().starts_with("<html") {
+        return Err(PiholeError::JsonError(format!(
+            "{} returned an HTML response (likely a login page)",
+            api_type
+        )));
+    }
 
-                // Log first 200 characters of response for debugging
-                let preview = if response_text.len() > 200 {
-                    format!("{}...", &response_text[..200])
-                } else {
-                    response_text.clone()
-                };
-                debug!("{} response preview: {}", api_type, preview);
-
-                // Check if response is HTML (likely a login page)
-                if response_text.trim_start().starts_with("<!DOCTYPE") || response_text.trim_start().starts_with("<html") {
-                    debug!("{} returned HTML response (likely login page), trying next endpoint", api_type);
-                    continue;
-                }
+    Ok(response_text)
+}
 
-                // Try to parse as JSON
-                match serde_json::from_str::<PiholeStats>(&response_text) {
-                    Ok(stats) => {
-                        validate_pihole_response(&stats)?;
-                        info!(
-                            "Successfully retrieved Pi-hole stats using {}: status={}, blocked_today={}",
-                            api_type, stats.status, stats.ads_blocked_today
-                        );
-                        debug!("Full stats: {:?}", stats);
-                        return Ok(stats);
-                    }
-                    Err(e) => {
-                        debug!("{} JSON parsing failed: {}, trying next endpoint", api_type, e);
-                        continue;
-                    }
-                }
-            }
+/// Try each `(label, url)` pair in order, returning the first body that is a
+/// successful, non-empty, non-HTML response. Used to implement the
+/// new-API-then-legacy-API fallback for endpoints where both APIs return the
+/// same JSON shape (see [`get_pihole_stats_internal`]); endpoints whose v6 and
+/// legacy shapes differ fetch and parse each endpoint separately instead (see
+/// [`fetch_pihole_top_items`] and friends) so a parse failure also triggers
+/// the fallback.
+async fn fetch_pihole_response_text(
+    client: &reqwest::Client,
+    sid: Option<&str>,
+    endpoints: &[(&str, Url)],
+) -> Result<String, PiholeError> {
+    for (api_type, url) in endpoints.iter() {
+        match fetch_pihole_url_text(client, sid, api_type, url).await {
+            Ok(text) => return Ok(text),
             Err(e) => {
-                debug!("{} request failed: {}, trying next endpoint", api_type, e);
+                debug!("{} failed: {}, trying next endpoint", api_type, e);
                 continue;
             }
         }
@@ -352,13 +743,249 @@ pub async fn get_pihole_stats_internal(host: &str, password: Option<&str>) -> Re
     ))
 }
 
+/// Resolve the session ID to authenticate requests with, given the credential.
+/// A legacy token is already embedded in the legacy URL's query by
+/// [`parse_pihole_urls`]/[`build_pihole_legacy_url`], so only the v6 password
+/// flow needs a session exchange here.
+async fn resolve_pihole_sid(host: &str, credential: &PiholeCredential) -> Option<String> {
+    match credential {
+        PiholeCredential::Password(password) => {
+            match authenticate_pihole(host, Some(password)).await {
+                Ok(sid) => sid,
+                Err(e) => {
+                    debug!("Authentication failed, continuing without auth: {}", e);
+                    None
+                }
+            }
+        }
+        PiholeCredential::Token(_) | PiholeCredential::None => None,
+    }
+}
+
+/// Append an `&auth=<token>` pair to `query`, with `token` percent-encoded so
+/// a token containing `&`, `=`, or whitespace can't corrupt the query string.
+fn append_auth_token(query: &str, token: &str) -> String {
+    let mut serializer = url::form_urlencoded::Serializer::new(String::new());
+    serializer.append_pair("auth", token);
+    format!("{}&{}", query, serializer.finish())
+}
+
+/// Build the legacy `/admin/api.php` URL for `query`, appending `&auth=<token>`
+/// when authenticating with a [`PiholeCredential::Token`].
+fn build_pihole_legacy_url(host: &str, query: &str, credential: &PiholeCredential) -> Result<Url, PiholeError> {
+    let mut url = build_pihole_url(host, "/admin/api.php")?;
+    let full_query = match credential {
+        PiholeCredential::Token(token) => append_auth_token(query, token),
+        _ => query.to_string(),
+    };
+    url.set_query(Some(&full_query));
+    Ok(url)
+}
+
+/// Internal function for testing - gets pihole stats without Tauri command wrapper
+pub async fn get_pihole_stats_internal(
+    host: &str,
+    credential: PiholeCredential,
+) -> Result<PiholeStats, PiholeError> {
+    info!("Requesting Pi-hole stats from host: {}", host);
+
+    let (legacy_url, new_url) = parse_pihole_urls(host, &credential)?;
+    let client = create_http_client();
+    let sid = resolve_pihole_sid(host, &credential).await;
+
+    // Try new API first, then fall back to legacy API
+    let endpoints = [("new API", new_url), ("legacy API", legacy_url)];
+    let response_text = fetch_pihole_response_text(&client, sid.as_deref(), &endpoints).await?;
+
+    let stats: PiholeStats = serde_json::from_str(&response_text).map_err(|e| {
+        PiholeError::JsonError(format!("Failed to parse Pi-hole stats response: {}", e))
+    })?;
+    validate_pihole_response(&stats)?;
+
+    info!(
+        "Successfully retrieved Pi-hole stats: status={}, blocked_today={}",
+        stats.status, stats.ads_blocked_today
+    );
+    debug!("Full stats: {:?}", stats);
+    Ok(stats)
+}
+
+/// Validate that query type percentages add up to roughly 100%.
+fn validate_query_types(query_types: &PiholeQueryTypes) -> Result<(), PiholeError> {
+    if query_types.0.is_empty() {
+        return Ok(());
+    }
+
+    let total: f64 = query_types.0.values().sum();
+    if !(98.0..=102.0).contains(&total) {
+        return Err(PiholeError::ValidationError {
+            reason: format!("Query type percentages sum to {:.1}%, expected ~100%", total),
+        });
+    }
+
+    Ok(())
+}
+
+/// Fetch top queried/blocked domains, trying the v6 API (which splits
+/// permitted and blocked domains across two calls to the same endpoint via a
+/// `blocked` query parameter) before falling back to the legacy endpoint,
+/// which returns both in one call.
+async fn fetch_pihole_top_items(
+    client: &reqwest::Client,
+    sid: Option<&str>,
+    host: &str,
+    credential: &PiholeCredential,
+) -> Result<PiholeTopItems, PiholeError> {
+    if let Ok(top_items) = fetch_pihole_top_items_v6(client, sid, host).await {
+        return Ok(top_items);
+    }
+
+    let legacy_url = build_pihole_legacy_url(host, "topItems", credential)?;
+    let text = fetch_pihole_url_text(client, sid, "legacy API", &legacy_url).await?;
+    let raw: RawPiholeTopItems = serde_json::from_str(&text)
+        .map_err(|e| PiholeError::JsonError(format!("Failed to parse legacy top items response: {}", e)))?;
+    Ok(raw.into())
+}
+
+async fn fetch_pihole_top_items_v6(
+    client: &reqwest::Client,
+    sid: Option<&str>,
+    host: &str,
+) -> Result<PiholeTopItems, PiholeError> {
+    let permitted_url = build_pihole_url(host, "/api/stats/top_domains")?;
+    let permitted_text = fetch_pihole_url_text(client, sid, "new API", &permitted_url).await?;
+    let permitted: RawPiholeTopDomainsV6 = serde_json::from_str(&permitted_text)
+        .map_err(|e| PiholeError::JsonError(format!("Failed to parse v6 top domains response: {}", e)))?;
+
+    let mut blocked_url = build_pihole_url(host, "/api/stats/top_domains")?;
+    blocked_url.set_query(Some("blocked=true"));
+    let blocked_text = fetch_pihole_url_text(client, sid, "new API", &blocked_url).await?;
+    let blocked: RawPiholeTopDomainsV6 = serde_json::from_str(&blocked_text)
+        .map_err(|e| PiholeError::JsonError(format!("Failed to parse v6 top blocked domains response: {}", e)))?;
+
+    Ok(PiholeTopItems {
+        top_queries: top_domains_v6_to_series(permitted),
+        top_ads: top_domains_v6_to_series(blocked),
+    })
+}
+
+/// Fetch query-type percentages, trying the v6 API before falling back to the
+/// legacy endpoint.
+async fn fetch_pihole_query_types(
+    client: &reqwest::Client,
+    sid: Option<&str>,
+    host: &str,
+    credential: &PiholeCredential,
+) -> Result<PiholeQueryTypes, PiholeError> {
+    if let Ok(query_types) = fetch_pihole_query_types_v6(client, sid, host).await {
+        return Ok(query_types);
+    }
+
+    let legacy_url = build_pihole_legacy_url(host, "getQueryTypes", credential)?;
+    let text = fetch_pihole_url_text(client, sid, "legacy API", &legacy_url).await?;
+    let raw: RawPiholeQueryTypes = serde_json::from_str(&text)
+        .map_err(|e| PiholeError::JsonError(format!("Failed to parse legacy query types response: {}", e)))?;
+    Ok(PiholeQueryTypes(raw.querytypes))
+}
+
+async fn fetch_pihole_query_types_v6(
+    client: &reqwest::Client,
+    sid: Option<&str>,
+    host: &str,
+) -> Result<PiholeQueryTypes, PiholeError> {
+    let url = build_pihole_url(host, "/api/stats/query_types")?;
+    let text = fetch_pihole_url_text(client, sid, "new API", &url).await?;
+    let raw: RawPiholeQueryTypesV6 = serde_json::from_str(&text)
+        .map_err(|e| PiholeError::JsonError(format!("Failed to parse v6 query types response: {}", e)))?;
+    Ok(PiholeQueryTypes(counts_to_percentages(raw.types)))
+}
+
+/// Fetch query/ad-block history, trying the v6 API before falling back to the
+/// legacy endpoint.
+async fn fetch_pihole_over_time(
+    client: &reqwest::Client,
+    sid: Option<&str>,
+    host: &str,
+    credential: &PiholeCredential,
+) -> Result<PiholeOverTime, PiholeError> {
+    if let Ok(over_time) = fetch_pihole_over_time_v6(client, sid, host).await {
+        return Ok(over_time);
+    }
+
+    let legacy_url = build_pihole_legacy_url(host, "overTimeData10mins", credential)?;
+    let text = fetch_pihole_url_text(client, sid, "legacy API", &legacy_url).await?;
+    let raw: RawPiholeOverTime = serde_json::from_str(&text)
+        .map_err(|e| PiholeError::JsonError(format!("Failed to parse legacy over-time response: {}", e)))?;
+    over_time_from_raw(raw)
+}
+
+async fn fetch_pihole_over_time_v6(
+    client: &reqwest::Client,
+    sid: Option<&str>,
+    host: &str,
+) -> Result<PiholeOverTime, PiholeError> {
+    let url = build_pihole_url(host, "/api/history")?;
+    let text = fetch_pihole_url_text(client, sid, "new API", &url).await?;
+    let raw: RawPiholeOverTimeV6 = serde_json::from_str(&text)
+        .map_err(|e| PiholeError::JsonError(format!("Failed to parse v6 over-time response: {}", e)))?;
+    Ok(over_time_from_raw_v6(raw))
+}
+
+/// Internal function for testing - fetches rich Pi-hole telemetry (top domains,
+/// top blocked domains, query types, and query/ad history) without the Tauri
+/// command wrapper.
+///
+/// Each of the three telemetry kinds is fetched and parsed independently,
+/// since the v6 and legacy APIs return different JSON shapes for the same
+/// data; a v6 response that parses successfully at the HTTP level but fails
+/// to match the expected shape still falls back to the legacy endpoint.
+pub async fn get_pihole_details_internal(
+    host: &str,
+    credential: PiholeCredential,
+) -> Result<PiholeDetails, PiholeError> {
+    info!("Requesting Pi-hole details from host: {}", host);
+
+    let client = create_http_client();
+    let sid = resolve_pihole_sid(host, &credential).await;
+
+    let top_items = fetch_pihole_top_items(&client, sid.as_deref(), host, &credential).await?;
+    let query_types = fetch_pihole_query_types(&client, sid.as_deref(), host, &credential).await?;
+    validate_query_types(&query_types)?;
+    let over_time = fetch_pihole_over_time(&client, sid.as_deref(), host, &credential).await?;
+
+    info!("Successfully retrieved Pi-hole details from {}", host);
+    Ok(PiholeDetails { top_items, query_types, over_time })
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     env_logger::init();
     info!("Starting NetScene application");
+
+    // Headless mode: serve scans and Pi-hole stats over HTTP instead of
+    // launching the Tauri UI, for users polling NetScene from dashboards.
+    // `--pihole-host=<host>` pins the one Pi-hole the Pi-hole summary route
+    // is allowed to proxy to; without it that route is disabled, since the
+    // `host` query parameter is untrusted input and the endpoint would
+    // otherwise fetch whatever host a caller names.
+    if std::env::args().any(|arg| arg == "--serve") {
+        info!("Starting headless HTTP API (--serve)");
+        let pihole_host = std::env::args()
+            .find_map(|arg| arg.strip_prefix("--pihole-host=").map(|host| host.to_string()));
+        tauri::async_runtime::block_on(server::serve(pihole_host));
+        return;
+    }
+
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
-        .invoke_handler(tauri::generate_handler![scan_network, get_pihole_stats])
+        .invoke_handler(tauri::generate_handler![
+            scan_network,
+            get_pihole_stats,
+            get_pihole_details,
+            set_pihole_blocking,
+            store_pihole_credential,
+            load_pihole_credential
+        ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
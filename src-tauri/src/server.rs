@@ -0,0 +1,184 @@
+//! Optional headless HTTP API, enabled via the `--serve` flag in [`crate::run`].
+//!
+//! Lets users running NetScene on a home server poll it from dashboards
+//! without driving the Tauri UI. Exposes the same scan and Pi-hole summary
+//! data as JSON, with gzip/deflate compression when the client asks for it.
+
+use axum::body::Body;
+use axum::extract::{Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::Response;
+use axum::routing::get;
+use axum::Router;
+use flate2::write::{DeflateEncoder, GzEncoder};
+use flate2::Compression;
+use log::info;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::io::Write;
+use std::net::SocketAddr;
+
+use crate::{
+    get_pihole_stats_internal, parse_pihole_urls, scan_network_internal, NetworkScanError,
+    PiholeCredential, PiholeError,
+};
+
+/// Loopback-only bind address for the headless API.
+const BIND_ADDR: &str = "127.0.0.1:3939";
+
+/// Shared state for the headless API's routes.
+#[derive(Clone)]
+struct AppState {
+    /// The only Pi-hole host `/api/pihole/summary` is allowed to proxy to.
+    /// `None` means no host was configured at startup, so the endpoint is
+    /// disabled rather than acting as an open proxy to any host a caller
+    /// names in the query string.
+    allowed_pihole_host: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PiholeSummaryQuery {
+    host: String,
+}
+
+/// Map a subsystem error onto the HTTP status code its response should carry.
+trait ApiError {
+    fn status_code(&self) -> StatusCode;
+}
+
+impl ApiError for NetworkScanError {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+}
+
+impl ApiError for PiholeError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            PiholeError::InvalidHost(_) | PiholeError::InvalidUrl(_) => StatusCode::BAD_REQUEST,
+            PiholeError::NetworkError(_)
+            | PiholeError::JsonError(_)
+            | PiholeError::ServerError { .. } => StatusCode::BAD_GATEWAY,
+            PiholeError::ValidationError { .. } => StatusCode::BAD_GATEWAY,
+            PiholeError::KeychainError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+/// Start the headless HTTP API and block until it stops serving.
+///
+/// `allowed_pihole_host`, set via `--pihole-host=<host>` in [`crate::run`], is
+/// the only host `/api/pihole/summary` will proxy requests to. Without it,
+/// the endpoint refuses every request rather than fetching whatever host a
+/// caller names in the query string — the `host` query parameter is
+/// untrusted input, so treating it as a free-form fetch target would make
+/// this an open SSRF proxy onto the local network.
+pub async fn serve(allowed_pihole_host: Option<String>) {
+    let state = AppState { allowed_pihole_host };
+    let app = Router::new()
+        .route("/api/devices", get(get_devices))
+        .route("/api/pihole/summary", get(get_pihole_summary))
+        .with_state(state);
+
+    let addr: SocketAddr = BIND_ADDR.parse().expect("invalid headless API bind address");
+    info!("Headless API listening on {}", addr);
+
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .expect("failed to bind headless API listener");
+    axum::serve(listener, app)
+        .await
+        .expect("headless API server error");
+}
+
+async fn get_devices(headers: HeaderMap) -> Response {
+    to_response(&headers, scan_network_internal().await)
+}
+
+async fn get_pihole_summary(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<PiholeSummaryQuery>,
+) -> Response {
+    to_response(
+        &headers,
+        fetch_pihole_summary(&query.host, state.allowed_pihole_host.as_deref()).await,
+    )
+}
+
+async fn fetch_pihole_summary(
+    host: &str,
+    allowed_host: Option<&str>,
+) -> Result<crate::PiholeStats, PiholeError> {
+    match allowed_host {
+        Some(allowed) if host.trim().eq_ignore_ascii_case(allowed.trim()) => {}
+        Some(_) => {
+            return Err(PiholeError::InvalidHost(format!(
+                "Host {} is not the configured Pi-hole host",
+                host
+            )));
+        }
+        None => {
+            return Err(PiholeError::InvalidHost(
+                "Headless API requires --pihole-host=<host> to be set; refusing to proxy to an arbitrary host".to_string(),
+            ));
+        }
+    }
+
+    parse_pihole_urls(host, &PiholeCredential::None)?;
+    get_pihole_stats_internal(host, PiholeCredential::None).await
+}
+
+/// Serialize `result` to JSON, map errors to their HTTP status, and compress
+/// the body if the client sent a supported `Accept-Encoding`.
+fn to_response<T, E>(headers: &HeaderMap, result: Result<T, E>) -> Response
+where
+    T: Serialize,
+    E: ApiError + std::fmt::Display,
+{
+    let (status, body) = match result {
+        Ok(value) => (
+            StatusCode::OK,
+            serde_json::to_vec(&value).expect("serialize headless API response"),
+        ),
+        Err(e) => (
+            e.status_code(),
+            serde_json::to_vec(&json!({ "error": e.to_string() }))
+                .expect("serialize headless API error response"),
+        ),
+    };
+
+    encode_response(status, body, headers)
+}
+
+/// Apply gzip or deflate compression based on the request's `Accept-Encoding`
+/// header, preferring gzip when both are accepted.
+fn encode_response(status: StatusCode, body: Vec<u8>, headers: &HeaderMap) -> Response {
+    let accept_encoding = headers
+        .get(header::ACCEPT_ENCODING)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("");
+
+    let (encoding, compressed) = if accept_encoding.contains("gzip") {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&body).expect("gzip encode response body");
+        (Some("gzip"), encoder.finish().expect("finish gzip stream"))
+    } else if accept_encoding.contains("deflate") {
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&body).expect("deflate encode response body");
+        (Some("deflate"), encoder.finish().expect("finish deflate stream"))
+    } else {
+        (None, body)
+    };
+
+    let mut builder = Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "application/json");
+    if let Some(encoding) = encoding {
+        builder = builder.header(header::CONTENT_ENCODING, encoding);
+    }
+
+    builder
+        .body(Body::from(compressed))
+        .expect("build headless API response")
+}